@@ -2,9 +2,11 @@
 
 use afio::MAPR;
 use cast::{u16, u8};
+use cortex_m::peripheral::DWT;
+use dma::dma1;
 use gpio::{Alternate, OpenDrain};
 use gpio::gpiob::{PB10, PB11, PB6, PB7, PB8, PB9};
-use hal::blocking::i2c::{Write, WriteRead};
+use hal::blocking::i2c::{Read, Write, WriteRead};
 use rcc::{APB1, Clocks};
 use stm32f103xx::{I2C1, I2C2};
 use time::Hertz;
@@ -18,12 +20,53 @@ pub enum Error {
     Arbitration,
     Acknowledge,
     Overrun,
+    /// Timed out waiting for a status flag (see `I2c::timeout`)
+    Timeout,
+    /// The given address falls in a range reserved by the I2C specification
+    AddressReserved,
+    /// The given address does not fit in 7 or 10 bits
+    AddressOutOfRange,
     // Pec, // SMBUS mode only
-    // Timeout, // SMBUS mode only
     // Alert, // SMBUS mode only
     #[doc(hidden)] _Extensible,
 }
 
+/// A target address, either 7-bit or 10-bit
+#[derive(Debug, Clone, Copy)]
+pub enum Address {
+    /// A 7-bit address occupying bits `[6:0]`
+    SevenBit(u8),
+    /// A 10-bit address occupying bits `[9:0]`
+    TenBit(u16),
+}
+
+impl Address {
+    fn validate(self) -> Result<(), Error> {
+        match self {
+            // 0b0000_xxx and 0b1111_xxx are reserved by the I2C specification
+            Address::SevenBit(addr) if addr & 0b1000_0000 != 0 => Err(Error::AddressOutOfRange),
+            Address::SevenBit(addr) if addr & 0b0111_1000 == 0 || addr & 0b0111_1000 == 0b0111_1000 => {
+                Err(Error::AddressReserved)
+            }
+            Address::SevenBit(_) => Ok(()),
+            Address::TenBit(addr) if addr > 0b11_1111_1111 => Err(Error::AddressOutOfRange),
+            Address::TenBit(_) => Ok(()),
+        }
+    }
+}
+
+impl From<u8> for Address {
+    fn from(addr: u8) -> Self {
+        Address::SevenBit(addr)
+    }
+}
+
+impl From<u16> for Address {
+    fn from(addr: u16) -> Self {
+        Address::TenBit(addr)
+    }
+}
+
 pub enum DutyCycle {
     Ratio1to1,
     Ratio16to9,
@@ -76,9 +119,14 @@ for (
 pub struct I2c<I2C, PINS> {
     i2c: I2C,
     pins: PINS,
+    /// Cycle-count budget allowed for a single status-flag wait inside `busy_wait!`
+    timeout: u32,
 }
 
 impl<PINS> I2c<I2C1, PINS> {
+    /// `timeout_us` bounds how long any single `busy_wait!` inside a transfer may spin,
+    /// measured with the DWT cycle counter (which this enables). Exceeding it yields
+    /// `Error::Timeout` instead of hanging forever on a stuck bus.
     pub fn i2c1(
         i2c: I2C1,
         pins: PINS,
@@ -86,48 +134,61 @@ impl<PINS> I2c<I2C1, PINS> {
         mode: Mode,
         clocks: Clocks,
         apb: &mut APB1,
+        dwt: &mut DWT,
+        timeout_us: u32,
     ) -> Self
         where
             PINS: Pins<I2C1>,
     {
         mapr.mapr().modify(|_, w| w.i2c1_remap().bit(PINS::REMAP));
-        I2c::_i2c1(i2c, pins, mode, clocks, apb)
+        I2c::_i2c1(i2c, pins, mode, clocks, apb, dwt, timeout_us)
     }
 }
 
 impl<PINS> I2c<I2C2, PINS> {
+    /// `timeout_us` bounds how long any single `busy_wait!` inside a transfer may spin,
+    /// measured with the DWT cycle counter (which this enables). Exceeding it yields
+    /// `Error::Timeout` instead of hanging forever on a stuck bus.
     pub fn i2c2(
         i2c: I2C2,
         pins: PINS,
         mode: Mode,
         clocks: Clocks,
         apb: &mut APB1,
+        dwt: &mut DWT,
+        timeout_us: u32,
     ) -> Self
         where
             PINS: Pins<I2C2>,
     {
-        I2c::_i2c2(i2c, pins, mode, clocks, apb)
+        I2c::_i2c2(i2c, pins, mode, clocks, apb, dwt, timeout_us)
     }
 }
 
 
 macro_rules! busy_wait {
-    ($i2c:expr, $flag:ident) => {
-        loop {
-            let isr = $i2c.sr1.read();
-
-            if isr.berr().bit_is_set() {
-                return Err(Error::Bus);
-            } else if isr.arlo().bit_is_set() {
-                return Err(Error::Arbitration);
-            } else if isr.af().bit_is_set() {
-                return Err(Error::Acknowledge);
-            } else if isr.ovr().bit_is_set() {
-                return Err(Error::Overrun);
-            } else if isr.$flag().bit_is_set() {
-                break;
-            } else {
-                // try again
+    ($i2c:expr, $flag:ident, $timeout:expr) => {
+        {
+            let start = DWT::get_cycle_count();
+
+            loop {
+                let isr = $i2c.sr1.read();
+
+                if isr.berr().bit_is_set() {
+                    return Err(Error::Bus);
+                } else if isr.arlo().bit_is_set() {
+                    return Err(Error::Arbitration);
+                } else if isr.af().bit_is_set() {
+                    return Err(Error::Acknowledge);
+                } else if isr.ovr().bit_is_set() {
+                    return Err(Error::Overrun);
+                } else if isr.$flag().bit_is_set() {
+                    break;
+                } else if DWT::get_cycle_count().wrapping_sub(start) > $timeout {
+                    return Err(Error::Timeout);
+                } else {
+                    // try again
+                }
             }
         }
     }
@@ -144,7 +205,11 @@ macro_rules! hal {
                     mode: Mode,
                     clocks: Clocks,
                     apb: &mut APB1,
+                    dwt: &mut DWT,
+                    timeout_us: u32,
                 ) -> Self {
+                    dwt.enable_cycle_counter();
+
                     apb.enr().modify(|_, w| w.$i2cXen().enabled());
                     apb.rstr().modify(|_, w| w.$i2cXrst().set_bit());
                     apb.rstr().modify(|_, w| w.$i2cXrst().clear_bit());
@@ -199,7 +264,9 @@ macro_rules! hal {
 
                     i2c.cr1.modify(|_, w| w.pe().set_bit());
 
-                    I2c { i2c, pins }
+                    let timeout = clocks.sysclk().0 / 1_000_000 * timeout_us;
+
+                    I2c { i2c, pins, timeout }
                 }
 
                 /// Releases the I2C peripheral and associated pins
@@ -208,30 +275,179 @@ macro_rules! hal {
                 }
             }
 
-            impl<PINS> Write for I2c<$I2CX, PINS> {
-                type Error = Error;
+            impl<PINS> I2c<$I2CX, PINS> {
+                /// Validates `address`, issues START and drives the bus through the addressing
+                /// phase of a transfer. For a 10-bit `read`, this also performs the repeated
+                /// START required to turn the addressing around (see `repeated_start_read`).
+                fn send_address(&mut self, address: Address, read: bool) -> Result<(), Error> {
+                    address.validate()?;
 
-                fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Error> {
-                    // TODO support transfers of more than 255 bytes
-                    assert!(bytes.len() < 256 && bytes.len() > 0);
+                    self.i2c.cr1.modify(|_, w| w.start().set_bit());
+                    busy_wait!(self.i2c, sb, self.timeout);
+
+                    match address {
+                        Address::SevenBit(addr) => {
+                            let rw = if read { 1 } else { 0 };
+                            self.i2c.dr.write(|w| unsafe { w.dr().bits((addr << 1) | rw) });
+                            busy_wait!(self.i2c, addr, self.timeout);
+                        }
+                        Address::TenBit(addr) => {
+                            let header = 0b1111_0000 | (((addr >> 8) as u8) << 1);
+
+                            self.i2c.dr.write(|w| unsafe { w.dr().bits(header) });
+                            busy_wait!(self.i2c, add10, self.timeout);
+
+                            self.i2c.dr.write(|w| unsafe { w.dr().bits(addr as u8) });
+                            busy_wait!(self.i2c, addr, self.timeout);
+
+                            if read {
+                                return self.repeated_start_read(address);
+                            }
+                        }
+                    }
+
+                    Ok(())
+                }
 
+                /// Turns the bus around for reading by issuing a repeated START followed by the
+                /// address with R/W set: the full 7-bit address, or for a 10-bit address the
+                /// header byte alone, per the ST reference manual
+                fn repeated_start_read(&mut self, address: Address) -> Result<(), Error> {
                     self.i2c.cr1.modify(|_, w| w.start().set_bit());
-                    busy_wait!(self.i2c, sb);
+                    busy_wait!(self.i2c, sb, self.timeout);
+
+                    match address {
+                        Address::SevenBit(addr) => {
+                            self.i2c.dr.write(|w| unsafe { w.dr().bits((addr << 1) | 1) });
+                        }
+                        Address::TenBit(addr) => {
+                            let header = 0b1111_0000 | (((addr >> 8) as u8) << 1);
+                            self.i2c.dr.write(|w| unsafe { w.dr().bits(header | 1) });
+                        }
+                    }
+                    busy_wait!(self.i2c, addr, self.timeout);
+
+                    Ok(())
+                }
 
-                    self.i2c.dr.write(|w| unsafe { w.dr().bits(addr & 0b1111_1110) });
-                    busy_wait!(self.i2c, addr);
+                /// Writes `bytes` to `address`
+                pub fn write(&mut self, address: impl Into<Address>, bytes: &[u8]) -> Result<(), Error> {
+                    assert!(bytes.len() > 0);
+
+                    self.send_address(address.into(), false)?;
                     let _ = self.i2c.sr2.read();
 
                     for byte in bytes {
-                        busy_wait!(self.i2c, tx_e);
+                        busy_wait!(self.i2c, tx_e, self.timeout);
                         self.i2c.dr.write(|w| unsafe { w.dr().bits(*byte) });
                     }
-                    busy_wait!(self.i2c, tx_e);
+                    busy_wait!(self.i2c, tx_e, self.timeout);
 
                     self.i2c.cr1.modify(|_, w| w.stop().set_bit());
 
                     Ok(())
                 }
+
+                /// Receives `buffer.len()` bytes from `address`, following the ST reference
+                /// procedure for each of the three distinct cases (1, 2 and >2 bytes) so that
+                /// ACK/NACK and STOP land on the correct byte boundary.
+                pub fn read(&mut self, address: impl Into<Address>, buffer: &mut [u8]) -> Result<(), Error> {
+                    self.send_address(address.into(), true)?;
+                    self.read_bytes(buffer)
+                }
+
+                /// Receives `buffer.len()` bytes assuming the addressing phase has already
+                /// completed (via `send_address` or `repeated_start_read`)
+                fn read_bytes(&mut self, buffer: &mut [u8]) -> Result<(), Error> {
+                    assert!(buffer.len() > 0);
+
+                    match buffer.len() {
+                        1 => {
+                            self.i2c.cr1.modify(|_, w| w.ack().clear_bit());
+                            let _ = self.i2c.sr2.read();
+                            self.i2c.cr1.modify(|_, w| w.stop().set_bit());
+
+                            busy_wait!(self.i2c, rx_ne, self.timeout);
+                            buffer[0] = self.i2c.dr.read().dr().bits();
+                        }
+                        2 => {
+                            self.i2c.cr1.modify(|_, w| w.pos().set_bit().ack().set_bit());
+                            let _ = self.i2c.sr2.read();
+                            self.i2c.cr1.modify(|_, w| w.ack().clear_bit());
+
+                            busy_wait!(self.i2c, btf, self.timeout);
+                            self.i2c.cr1.modify(|_, w| w.stop().set_bit());
+
+                            buffer[0] = self.i2c.dr.read().dr().bits();
+                            buffer[1] = self.i2c.dr.read().dr().bits();
+
+                            self.i2c.cr1.modify(|_, w| w.pos().clear_bit());
+                        }
+                        n => {
+                            self.i2c.cr1.modify(|_, w| w.ack().set_bit());
+                            let _ = self.i2c.sr2.read();
+
+                            for byte in &mut buffer[..n - 3] {
+                                busy_wait!(self.i2c, rx_ne, self.timeout);
+                                *byte = self.i2c.dr.read().dr().bits();
+                            }
+
+                            busy_wait!(self.i2c, btf, self.timeout);
+                            self.i2c.cr1.modify(|_, w| w.ack().clear_bit());
+                            buffer[n - 3] = self.i2c.dr.read().dr().bits();
+                            self.i2c.cr1.modify(|_, w| w.stop().set_bit());
+                            buffer[n - 2] = self.i2c.dr.read().dr().bits();
+
+                            busy_wait!(self.i2c, rx_ne, self.timeout);
+                            buffer[n - 1] = self.i2c.dr.read().dr().bits();
+                        }
+                    }
+
+                    Ok(())
+                }
+            }
+
+            impl<PINS> I2c<$I2CX, PINS> {
+                /// Writes `bytes` to `address`, then issues a repeated START and reads
+                /// `buffer.len()` bytes back
+                pub fn write_read(
+                    &mut self,
+                    address: impl Into<Address>,
+                    bytes: &[u8],
+                    buffer: &mut [u8],
+                ) -> Result<(), Error> {
+                    assert!(bytes.len() > 0);
+
+                    let address = address.into();
+
+                    self.send_address(address, false)?;
+                    let _ = self.i2c.sr2.read();
+
+                    for byte in bytes {
+                        busy_wait!(self.i2c, tx_e, self.timeout);
+                        self.i2c.dr.write(|w| unsafe { w.dr().bits(*byte) });
+                    }
+                    busy_wait!(self.i2c, tx_e, self.timeout);
+
+                    self.repeated_start_read(address)?;
+                    self.read_bytes(buffer)
+                }
+            }
+
+            impl<PINS> Write for I2c<$I2CX, PINS> {
+                type Error = Error;
+
+                fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Error> {
+                    I2c::write(self, addr, bytes)
+                }
+            }
+
+            impl<PINS> Read for I2c<$I2CX, PINS> {
+                type Error = Error;
+
+                fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Error> {
+                    I2c::read(self, addr, buffer)
+                }
             }
 
             impl<PINS> WriteRead for I2c<$I2CX, PINS> {
@@ -243,45 +459,404 @@ macro_rules! hal {
                     bytes: &[u8],
                     buffer: &mut [u8],
                 ) -> Result<(), Error> {
-                    // TODO support transfers of more than 255 bytes
-                    assert!(bytes.len() < 256 && bytes.len() > 0);
-                    assert!(buffer.len() < 256 && buffer.len() > 0);
+                    I2c::write_read(self, addr, bytes, buffer)
+                }
+            }
+        )+
+    }
+}
 
-                    self.i2c.cr1.modify(|_, w| w.start().set_bit());
-                    busy_wait!(self.i2c, sb);
+hal! {
+    I2C1: (_i2c1, i2c1en, i2c1rst),
+    I2C2: (_i2c2, i2c2en, i2c2rst),
+}
 
-                    self.i2c.dr.write(|w| unsafe { w.dr().bits(addr & 0b1111_1110) });
-                    busy_wait!(self.i2c, addr);
-                    let _ = self.i2c.sr2.read();
+/// Direction of the transaction a master started with us, as reported by `SR2`'s `TRA` bit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// The master is writing to us; call `read` next
+    Write,
+    /// The master is reading from us; call `respond` next
+    Read,
+}
 
-                    for byte in bytes {
-                        busy_wait!(self.i2c, tx_e);
-                        self.i2c.dr.write(|w| unsafe { w.dr().bits(*byte) });
+/// Reason an `I2cSlave` operation completed, analogous to embassy-rp's `AbortReason`
+#[derive(Debug)]
+pub enum Event {
+    /// The master addressed this device directly, in the given `Direction`
+    AddressMatch(Direction),
+    /// The master issued a general call (address `0x00`)
+    GeneralCall,
+    /// Every byte passed to `respond` was handed to the data register without the master
+    /// NACKing first
+    Complete,
+    /// The master NACKed a byte, ending the transfer before all bytes were sent
+    NotAcknowledged,
+}
+
+/// I2C peripheral operating in slave (device) mode
+pub struct I2cSlave<I2C, PINS> {
+    i2c: I2C,
+    pins: PINS,
+    timeout: u32,
+}
+
+impl<PINS> I2cSlave<I2C1, PINS> {
+    /// Configures the I2C peripheral to respond as a slave at the 7-bit `address`, and
+    /// optionally a `second_address` via `OAR2`
+    pub fn i2c1(
+        i2c: I2C1,
+        pins: PINS,
+        mapr: &mut MAPR,
+        address: u8,
+        second_address: Option<u8>,
+        clocks: Clocks,
+        apb: &mut APB1,
+        dwt: &mut DWT,
+        timeout_us: u32,
+    ) -> Self
+        where
+            PINS: Pins<I2C1>,
+    {
+        mapr.mapr().modify(|_, w| w.i2c1_remap().bit(PINS::REMAP));
+        I2cSlave::_i2c1(i2c, pins, address, second_address, clocks, apb, dwt, timeout_us)
+    }
+}
+
+impl<PINS> I2cSlave<I2C2, PINS> {
+    /// Configures the I2C peripheral to respond as a slave at the 7-bit `address`, and
+    /// optionally a `second_address` via `OAR2`
+    pub fn i2c2(
+        i2c: I2C2,
+        pins: PINS,
+        address: u8,
+        second_address: Option<u8>,
+        clocks: Clocks,
+        apb: &mut APB1,
+        dwt: &mut DWT,
+        timeout_us: u32,
+    ) -> Self
+        where
+            PINS: Pins<I2C2>,
+    {
+        I2cSlave::_i2c2(i2c, pins, address, second_address, clocks, apb, dwt, timeout_us)
+    }
+}
+
+macro_rules! hal_slave {
+    ($($I2CX:ident: ($i2cX:ident, $i2cXen:ident, $i2cXrst:ident),)+) => {
+        $(
+            impl<PINS> I2cSlave<$I2CX, PINS> {
+                /// Configures the I2C peripheral to work in slave mode
+                fn $i2cX(
+                    i2c: $I2CX,
+                    pins: PINS,
+                    address: u8,
+                    second_address: Option<u8>,
+                    clocks: Clocks,
+                    apb: &mut APB1,
+                    dwt: &mut DWT,
+                    timeout_us: u32,
+                ) -> Self {
+                    dwt.enable_cycle_counter();
+
+                    apb.enr().modify(|_, w| w.$i2cXen().enabled());
+                    apb.rstr().modify(|_, w| w.$i2cXrst().set_bit());
+                    apb.rstr().modify(|_, w| w.$i2cXrst().clear_bit());
+
+                    i2c.cr1.write(|w| w.pe().clear_bit());
+
+                    // Bit 14 of OAR1 isn't used by the peripheral but the reference manual
+                    // requires software to always keep it at 1
+                    i2c.oar1.write(|w| unsafe {
+                        w.bits((1 << 14) | ((address as u16) << 1))
+                    });
+
+                    match second_address {
+                        Some(address2) => {
+                            i2c.oar2.write(|w| unsafe {
+                                w.add2().bits(address2).endual().set_bit()
+                            });
+                        }
+                        None => {
+                            i2c.oar2.write(|w| w.endual().clear_bit());
+                        }
                     }
-                    busy_wait!(self.i2c, tx_e);
 
-                    self.i2c.cr1.modify(|_, w| w.start().set_bit());
-                    busy_wait!(self.i2c, sb);
+                    i2c.cr2.modify(|_, w| unsafe {
+                        w.
+                            last().clear_bit().
+                            dmaen().clear_bit().
+                            itbufen().clear_bit().
+                            itevten().clear_bit().
+                            iterren().clear_bit().
+                            freq().bits((clocks.pclk1().0 / 1000000) as u8)
+                    });
 
-                    self.i2c.dr.write(|w| unsafe { w.dr().bits(addr | 0b0000_0001) });
-                    busy_wait!(self.i2c, addr);
-                    let _ = self.i2c.sr2.read();
+                    i2c.cr1.modify(|_, w| w.ack().set_bit().engc().set_bit().pe().set_bit());
+
+                    let timeout = clocks.sysclk().0 / 1_000_000 * timeout_us;
+
+                    I2cSlave { i2c, pins, timeout }
+                }
+
+                /// Releases the I2C peripheral and associated pins
+                pub fn free(self) -> ($I2CX, PINS) {
+                    (self.i2c, self.pins)
+                }
+
+                /// Blocks until a master starts a transaction addressed to us, returning why we
+                /// were addressed. `Event::AddressMatch` carries the transaction `Direction`;
+                /// call `read` for `Direction::Write` or `respond` for `Direction::Read`.
+                pub fn listen(&mut self) -> Result<Event, Error> {
+                    loop {
+                        let sr1 = self.i2c.sr1.read();
+
+                        if sr1.berr().bit_is_set() {
+                            return Err(Error::Bus);
+                        } else if sr1.arlo().bit_is_set() {
+                            return Err(Error::Arbitration);
+                        } else if sr1.ovr().bit_is_set() {
+                            return Err(Error::Overrun);
+                        } else if sr1.addr().bit_is_set() {
+                            let sr2 = self.i2c.sr2.read();
+
+                            return if sr2.gencall().bit_is_set() {
+                                Ok(Event::GeneralCall)
+                            } else if sr2.tra().bit_is_set() {
+                                Ok(Event::AddressMatch(Direction::Read))
+                            } else {
+                                Ok(Event::AddressMatch(Direction::Write))
+                            };
+                        }
+                    }
+                }
+
+                /// Receives a master write into `buffer`, returning how many bytes were actually
+                /// written. A legal short write — e.g. a 1-byte register address followed
+                /// immediately by STOP — ends the transaction before `buffer` is filled; that is
+                /// reported by the returned count being less than `buffer.len()`, not as
+                /// `Error::Timeout`.
+                pub fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Error> {
+                    assert!(buffer.len() > 0);
+
+                    let start = DWT::get_cycle_count();
+
+                    for (received, byte) in buffer.iter_mut().enumerate() {
+                        loop {
+                            let sr1 = self.i2c.sr1.read();
+
+                            if sr1.berr().bit_is_set() {
+                                return Err(Error::Bus);
+                            } else if sr1.arlo().bit_is_set() {
+                                return Err(Error::Arbitration);
+                            } else if sr1.ovr().bit_is_set() {
+                                return Err(Error::Overrun);
+                            } else if sr1.rx_ne().bit_is_set() {
+                                // Checked before STOPF so a byte still waiting in DR when the
+                                // STOP arrives is read and counted
+                                break;
+                            } else if sr1.stopf().bit_is_set() {
+                                // Clearing STOPF is a read of SR1 (just done) followed by a
+                                // write to CR1
+                                self.i2c.cr1.modify(|_, w| w);
+                                return Ok(received);
+                            } else if DWT::get_cycle_count().wrapping_sub(start) > self.timeout {
+                                return Err(Error::Timeout);
+                            }
+                        }
 
-                    for byte in buffer {
-                        busy_wait!(self.i2c, rx_ne);
                         *byte = self.i2c.dr.read().dr().bits();
                     }
 
-                    self.i2c.cr1.modify(|_, w| w.stop().set_bit());
+                    Ok(buffer.len())
+                }
 
-                    Ok(())
+                /// Answers a master read with `bytes`, stopping early if the master NACKs
+                /// before all of `bytes` have been sent
+                pub fn respond(&mut self, bytes: &[u8]) -> Result<Event, Error> {
+                    assert!(bytes.len() > 0);
+
+                    let start = DWT::get_cycle_count();
+
+                    for byte in bytes {
+                        loop {
+                            let sr1 = self.i2c.sr1.read();
+
+                            if sr1.berr().bit_is_set() {
+                                return Err(Error::Bus);
+                            } else if sr1.arlo().bit_is_set() {
+                                return Err(Error::Arbitration);
+                            } else if sr1.af().bit_is_set() {
+                                self.i2c.sr1.modify(|_, w| w.af().clear_bit());
+                                return Ok(Event::NotAcknowledged);
+                            } else if sr1.ovr().bit_is_set() {
+                                return Err(Error::Overrun);
+                            } else if sr1.tx_e().bit_is_set() {
+                                break;
+                            } else if DWT::get_cycle_count().wrapping_sub(start) > self.timeout {
+                                return Err(Error::Timeout);
+                            }
+                        }
+
+                        self.i2c.dr.write(|w| unsafe { w.dr().bits(*byte) });
+                    }
+
+                    Ok(Event::Complete)
                 }
             }
         )+
     }
 }
 
-hal! {
+hal_slave! {
     I2C1: (_i2c1, i2c1en, i2c1rst),
     I2C2: (_i2c2, i2c2en, i2c2rst),
+}
+
+/// A DMA-driven I2C transfer, returned by `write_dma`/`read_dma`. Owns the I2C peripheral,
+/// DMA channel and buffer until `wait` hands them back.
+pub struct Transfer<I2C, PINS, CHANNEL, BUFFER> {
+    i2c: I2c<I2C, PINS>,
+    channel: CHANNEL,
+    buffer: BUFFER,
+    /// `true` for a `write_dma` transfer, `false` for a `read_dma` transfer. The DMA controller
+    /// only shifts bytes out to `DR`; it never issues the STOP condition that ends a transmit,
+    /// so `wait` has to do that itself once the last byte has been acknowledged.
+    write: bool,
+}
+
+impl<I2C, PINS, CHANNEL, BUFFER> Transfer<I2C, PINS, CHANNEL, BUFFER>
+where
+    CHANNEL: dma1::DmaChannel,
+{
+    /// True once the DMA channel has moved every byte
+    pub fn is_done(&self) -> bool {
+        !self.channel.in_progress()
+    }
+
+    /// Blocks until the DMA channel finishes, then surfaces any bus/arbitration/ack/overrun
+    /// error the peripheral raised and returns the I2C peripheral, DMA channel and buffer for
+    /// reuse. For a `write_dma` transfer this also waits for `BTF` and issues the STOP condition,
+    /// since the DMA controller itself never does. Both waits are bounded by `I2c::timeout`,
+    /// the same budget `busy_wait!` enforces elsewhere, so a stalled channel or a bus that never
+    /// reaches `BTF` yields `Error::Timeout` instead of hanging forever.
+    pub fn wait(mut self) -> Result<(I2c<I2C, PINS>, CHANNEL, BUFFER), Error> {
+        let timeout = self.i2c.timeout;
+
+        let start = DWT::get_cycle_count();
+        while self.channel.in_progress() {
+            if DWT::get_cycle_count().wrapping_sub(start) > timeout {
+                return Err(Error::Timeout);
+            }
+        }
+        self.channel.stop();
+
+        if self.write {
+            let start = DWT::get_cycle_count();
+            while self.i2c.i2c.sr1.read().btf().bit_is_clear() {
+                if DWT::get_cycle_count().wrapping_sub(start) > timeout {
+                    return Err(Error::Timeout);
+                }
+            }
+            self.i2c.i2c.cr1.modify(|_, w| w.stop().set_bit());
+        }
+
+        let sr1 = self.i2c.i2c.sr1.read();
+
+        if sr1.berr().bit_is_set() {
+            Err(Error::Bus)
+        } else if sr1.arlo().bit_is_set() {
+            Err(Error::Arbitration)
+        } else if sr1.af().bit_is_set() {
+            Err(Error::Acknowledge)
+        } else if sr1.ovr().bit_is_set() {
+            Err(Error::Overrun)
+        } else {
+            // Restore the clean CR2 state the constructor establishes so the peripheral is
+            // ready for reuse: DMAEN drove this transfer and LAST (read_dma only) would
+            // otherwise auto-NACK the very next byte of whatever comes next.
+            self.i2c.i2c.cr2.modify(|_, w| w.dmaen().clear_bit().last().clear_bit());
+
+            Ok((self.i2c, self.channel, self.buffer))
+        }
+    }
+}
+
+macro_rules! hal_dma {
+    ($($I2CX:ident: ($txchannel:ty, $rxchannel:ty),)+) => {
+        $(
+            impl<PINS> I2c<$I2CX, PINS> {
+                /// Writes `buffer` to `address`, handing the bytes off to `channel` so the DMA
+                /// controller moves them instead of the CPU polling `tx_e`. On error the I2C
+                /// peripheral, channel and buffer are handed back so the caller can retry
+                /// instead of losing the peripheral.
+                pub fn write_dma<B>(
+                    mut self,
+                    address: impl Into<Address>,
+                    buffer: B,
+                    mut channel: $txchannel,
+                ) -> Result<Transfer<$I2CX, PINS, $txchannel, B>, (Self, $txchannel, B, Error)>
+                    where
+                        B: AsRef<[u8]>,
+                {
+                    if let Err(e) = self.send_address(address.into(), false) {
+                        return Err((self, channel, buffer, e));
+                    }
+                    let _ = self.i2c.sr2.read();
+
+                    unsafe {
+                        let bytes = buffer.as_ref();
+                        channel.set_peripheral_address(&self.i2c.dr as *const _ as u32, false);
+                        channel.set_memory_address(bytes.as_ptr() as u32, true);
+                        channel.set_transfer_length(bytes.len());
+                    }
+
+                    self.i2c.cr2.modify(|_, w| w.dmaen().set_bit());
+                    channel.start();
+
+                    Ok(Transfer { i2c: self, channel, buffer, write: true })
+                }
+
+                /// Reads `buffer.len()` bytes from `address` via `channel`. Sets the `last` bit
+                /// so the controller auto-NACKs and STOPs on the final byte without CPU
+                /// intervention. On error the I2C peripheral, channel and buffer are handed
+                /// back so the caller can retry instead of losing the peripheral.
+                pub fn read_dma<B>(
+                    mut self,
+                    address: impl Into<Address>,
+                    mut buffer: B,
+                    mut channel: $rxchannel,
+                ) -> Result<Transfer<$I2CX, PINS, $rxchannel, B>, (Self, $rxchannel, B, Error)>
+                    where
+                        B: AsMut<[u8]>,
+                {
+                    if let Err(e) = self.send_address(address.into(), true) {
+                        return Err((self, channel, buffer, e));
+                    }
+                    let _ = self.i2c.sr2.read();
+
+                    self.i2c.cr1.modify(|_, w| w.ack().set_bit());
+                    self.i2c.cr2.modify(|_, w| w.last().set_bit());
+
+                    unsafe {
+                        let bytes = buffer.as_mut();
+                        channel.set_peripheral_address(&self.i2c.dr as *const _ as u32, false);
+                        channel.set_memory_address(bytes.as_mut_ptr() as u32, true);
+                        channel.set_transfer_length(bytes.len());
+                    }
+
+                    self.i2c.cr2.modify(|_, w| w.dmaen().set_bit());
+                    channel.start();
+
+                    Ok(Transfer { i2c: self, channel, buffer, write: false })
+                }
+            }
+        )+
+    }
+}
+
+hal_dma! {
+    I2C1: (dma1::C6, dma1::C7),
+    I2C2: (dma1::C4, dma1::C5),
 }
\ No newline at end of file